@@ -2,9 +2,10 @@
 
 //! Provides an easy interface to preserve the insertion order of your `HashMap`.
 
-use std::collections::HashMap;
+use std::borrow::Borrow;
+use std::collections::{hash_map, HashMap};
 use std::fmt::{Debug, Formatter, Result};
-use std::hash::Hash;
+use std::hash::{BuildHasher, Hash, RandomState};
 use traits::Keys;
 
 /// Stores an index for quick key lookup and the value.
@@ -14,55 +15,171 @@ pub struct IndexedLinkedHashMapValue<V> {
     pub value: V,
 }
 
-/// Stores number of keys, keys in order, and values.
-pub struct IndexedLinkedHashMap<I, K, V> {
+/// Stores number of keys, keys in order, and values. Generic over the hasher `S`
+/// used by the value store, like `std::collections::HashMap`, so embedded targets
+/// can plug in a cheaper non-DoS-resistant hasher via `with_hasher`.
+pub struct IndexedLinkedHashMap<I, K, V, S = RandomState> {
     _keys: I,
-    _values: HashMap<K, IndexedLinkedHashMapValue<V>>,
+    _values: HashMap<K, IndexedLinkedHashMapValue<V>, S>,
+    _capacity: Option<usize>,
 }
 
-impl<I, K, V> IndexedLinkedHashMap<I, K, V>
+impl<I, K, V, S> IndexedLinkedHashMap<I, K, V, S>
 where
     I: Keys<K> + Default,
     K: Eq + Hash + Clone,
     V: Clone,
+    S: BuildHasher + Default,
 {
     /// Creates new `IndexedLinkedHashMap`.
     pub fn new() -> Self {
         return IndexedLinkedHashMap {
             _keys: I::default(),
-            _values: HashMap::new(),
+            _values: HashMap::default(),
+            _capacity: None,
         };
     }
 
-    /// Gets value using key; returns `Some(v)` if exists or `None`.
-    pub fn get(&self, k: K) -> Option<&V> {
-        return match self._values.get(&k) {
+    /// Creates a new `IndexedLinkedHashMap` in LRU eviction mode: once `set`
+    /// would grow the map past `capacity`, the least-recently-used entry (the
+    /// front of `_keys`) is evicted automatically. Reuses `_keys`'s own
+    /// ordering as the recency queue, so `touch`/`get_refresh` just move a key
+    /// to the back and `pop_front` removes the front.
+    pub fn with_capacity_lru(capacity: usize) -> Self {
+        return IndexedLinkedHashMap {
+            _keys: I::default(),
+            _values: HashMap::default(),
+            _capacity: Some(capacity),
+        };
+    }
+
+    /// Gets value using key; returns `Some(v)` if exists or `None`. Accepts any
+    /// borrowed form of the key (e.g. `&str` for a `String`-keyed map) so callers
+    /// don't need to allocate an owned key just to look one up.
+    pub fn get<Q>(&self, k: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        return match self._values.get(k) {
             Some(v) => Some(&v.value),
             None => None,
         };
     }
 
-    /// Sets value; upserts if exists already or adds new entry.
+    /// Gets value using key, same as `get`, and also `touch`es it, moving it
+    /// to the most-recently-used position. Used with `with_capacity_lru` so
+    /// reads, not just writes, count toward recency.
+    pub fn get_refresh<Q>(&mut self, k: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.touch(k);
+
+        return self.get(k);
+    }
+
+    /// Moves `k` to the most-recently-used position, the back of `_keys`, if
+    /// present; returns whether the key was found. Reuses `_keys`'s ordering
+    /// as the recency queue, the same way `set` and `pop_front` do for the
+    /// LRU eviction mode.
+    pub fn touch<Q>(&mut self, k: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let index = match self._values.get(k) {
+            Some(value) => match value.index {
+                Some(index) => index,
+                None => return false,
+            },
+            None => return false,
+        };
+
+        if let Some(key) = self._keys.get(Some(index)).cloned() {
+            self._keys.remove(Some(index));
+            self.resync_indices_from(index);
+
+            // `key` was already present, so the backing just had room for it
+            // freed up by the `remove` above; this can't fail.
+            let _ = self._keys.push(key.clone());
+
+            let new_index = self._keys.len() - 1;
+            if let Some(value) = self._values.get_mut::<K>(&key) {
+                value.index = Some(new_index);
+            }
+        }
+
+        return true;
+    }
+
+    /// Sets value; upserts if exists already or adds new entry, via `entry`
+    /// instead of a separate `contains_key`/`get`/`insert` sequence. In LRU
+    /// eviction mode (see `with_capacity_lru`), adding a new entry past
+    /// `_capacity` evicts the least-recently-used entry up front. If `_keys`
+    /// is a capacity-bounded backing (e.g. `collections::ArrayKeys`) that is
+    /// already full, a new key is silently not inserted, the same no-op
+    /// behavior as `Keys::push` itself; use `try_set` to detect that instead.
+    /// In LRU eviction mode, overwriting an existing key also `touch`es it, so
+    /// a write counts as a use, the same as `get_refresh`; otherwise an
+    /// overwritten key keeps its position.
     pub fn set(&mut self, k: K, v: V) {
-        if self._values.contains_key(&k) {
-            let value: &IndexedLinkedHashMapValue<V> = self._values.get(&k).unwrap();
-            self._values.insert(
-                k,
-                IndexedLinkedHashMapValue {
-                    index: value.index,
-                    value: v,
-                },
-            );
-        } else {
-            self._keys.push(k.to_owned());
-            self._values.insert(
-                k,
-                IndexedLinkedHashMapValue {
-                    index: Some(self._keys.len() - 1),
-                    value: v,
-                },
-            );
+        let touch_key = k.clone();
+        let occupied = match self.entry(k) {
+            Entry::Occupied(mut entry) => {
+                *entry.get_mut() = v;
+                true
+            }
+            Entry::Vacant(entry) => {
+                let _ = entry.insert(v);
+                false
+            }
+        };
+
+        if occupied && self._capacity.is_some() {
+            self.touch(&touch_key);
+        }
+    }
+
+    /// Sets value, failing instead of growing past `_keys`'s capacity, if it has
+    /// one. `Vec`- and `BinaryHeap`-backed maps report no capacity limit and this
+    /// always succeeds; capacity-bounded backings like `collections::ArrayKeys`
+    /// return the key/value back on `Err` once full instead of silently dropping
+    /// the key the way `push` would.
+    pub fn try_set(&mut self, k: K, v: V) -> std::result::Result<(), (K, V)> {
+        if !self._values.contains_key(&k) {
+            if let Some(capacity) = self._keys.capacity() {
+                if self._keys.len() >= capacity {
+                    return Err((k, v));
+                }
+            }
+        }
+
+        self.set(k, v);
+
+        return Ok(());
+    }
+
+    /// Gets the entry for a key, allowing in-place insertion or mutation without a
+    /// separate `contains_key`/`get`/`insert` sequence. Mirrors
+    /// `std::collections::HashMap::entry` while preserving `_keys` order: a vacant
+    /// entry that is inserted into is pushed onto `_keys` exactly like `set`. In
+    /// LRU eviction mode (see `with_capacity_lru`), a vacant entry for a new key
+    /// evicts the least-recently-used entry up front, the same as `set` does,
+    /// so the returned `VacantEntry` always has room to insert into.
+    pub fn entry(&mut self, k: K) -> Entry<'_, I, K, V> {
+        if let Some(capacity) = self._capacity {
+            if !self._values.contains_key(&k) && self._keys.len() >= capacity {
+                self.pop_front();
+            }
         }
+
+        let keys = &mut self._keys;
+        return match self._values.entry(k) {
+            hash_map::Entry::Occupied(inner) => Entry::Occupied(OccupiedEntry { inner }),
+            hash_map::Entry::Vacant(inner) => Entry::Vacant(VacantEntry { inner, keys }),
+        };
     }
 
     /// Gets value using index; returns `Some(v)` if exists or `None`.
@@ -123,16 +240,89 @@ where
         };
     }
 
-    /// Removes value; returns `Some(v)` if exists or `None`.
-    pub fn remove(&mut self, k: K) -> Option<IndexedLinkedHashMapValue<V>> {
-        if self._values.contains_key(&k) {
-            let removed = self._values.remove(&k).unwrap();
-            self._keys.remove(removed.index);
+    /// Removes value; returns `Some(v)` if exists or `None`. Accepts any borrowed
+    /// form of the key, same as `get`. Alias of `shift_remove`, kept so existing
+    /// callers keep the order-preserving behavior they already depend on.
+    pub fn remove<Q>(&mut self, k: &Q) -> Option<IndexedLinkedHashMapValue<V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        return self.shift_remove(k);
+    }
 
-            return Some(removed);
-        }
+    /// Removes value, shifting every key after it left by one, same as
+    /// `Vec::remove`. O(n) in the number of keys after the removed one, since
+    /// each shifted key's stored `index` must be re-synced to its new position.
+    pub fn shift_remove<Q>(&mut self, k: &Q) -> Option<IndexedLinkedHashMapValue<V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        return match self._values.remove(k) {
+            Some(removed) => {
+                if let Some(index) = removed.index {
+                    self._keys.remove(Some(index));
+                    self.resync_indices_from(index);
+                }
 
-        return None;
+                Some(removed)
+            }
+            None => None,
+        };
+    }
+
+    /// Removes value by moving the last key into its slot, same as
+    /// `Vec::swap_remove`. O(1), since only the moved key's stored `index` needs
+    /// to be updated, but does not preserve the relative order of the remaining
+    /// keys.
+    pub fn swap_remove<Q>(&mut self, k: &Q) -> Option<IndexedLinkedHashMapValue<V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        return match self._values.remove(k) {
+            Some(removed) => {
+                if let Some(index) = removed.index {
+                    let last = self._keys.len() - 1;
+                    if index != last {
+                        if let Some(moved_key) = self._keys.get(Some(last)).cloned() {
+                            self._keys.set(Some(index), moved_key.clone());
+                            if let Some(value) = self._values.get_mut::<K>(&moved_key) {
+                                value.index = Some(index);
+                            }
+                        }
+                    }
+
+                    self._keys.remove(Some(last));
+                }
+
+                Some(removed)
+            }
+            None => None,
+        };
+    }
+
+    /// Removes and returns the least-recently-used entry, the front of
+    /// `_keys`. Used to implement the LRU eviction mode, and can also be
+    /// called directly to evict manually.
+    pub fn pop_front(&mut self) -> Option<(K, V)> {
+        let k = self.key_at(Some(0))?.to_owned();
+        let removed = self.shift_remove(&k)?;
+
+        return Some((k, removed.value));
+    }
+
+    /// Re-syncs the stored `index` of every key from `from` to the end of
+    /// `_keys`, used after a shift removal moves them all left by one.
+    fn resync_indices_from(&mut self, from: usize) {
+        for i in from..self._keys.len() {
+            if let Some(key) = self._keys.get(Some(i)).cloned() {
+                if let Some(value) = self._values.get_mut::<K>(&key) {
+                    value.index = Some(i);
+                }
+            }
+        }
     }
 
     /// Clears all values.
@@ -146,9 +336,13 @@ where
         return self._keys.len();
     }
 
-    /// Check if contains a key.
-    pub fn contains_key(&self, k: K) -> bool {
-        return self._values.contains_key(&k);
+    /// Check if contains a key. Accepts any borrowed form of the key, same as `get`.
+    pub fn contains_key<Q>(&self, k: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        return self._values.contains_key(k);
     }
 
     /// Gets all keys.
@@ -163,13 +357,191 @@ where
             .values()
             .collect::<Vec<&IndexedLinkedHashMapValue<V>>>();
     }
+
+    /// Iterates key/value pairs in insertion order in one O(n) pass over `_keys`,
+    /// instead of calling `at` once per index, which is O(n^2) for backings whose
+    /// `get` walks the keys, like `BinaryHeap`.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        return self
+            ._keys
+            .iter()
+            .filter_map(move |k| self._values.get(k).map(|v| (k, &v.value)));
+    }
+
+    /// Iterates key/value pairs in insertion order, with a mutable reference to
+    /// each value.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&K, &mut V)> {
+        let values: *mut HashMap<K, IndexedLinkedHashMapValue<V>, S> = &mut self._values;
+
+        // SAFETY: `_keys` holds every key in `_values` exactly once, so the
+        // `&mut V` produced for each key never aliases the `&mut V` of another.
+        return self
+            ._keys
+            .iter()
+            .filter_map(move |k| unsafe { (*values).get_mut(k).map(|v| (k, &mut v.value)) });
+    }
+
+    /// Removes and iterates every key/value pair in insertion order, leaving the
+    /// map empty.
+    pub fn drain(&mut self) -> std::vec::IntoIter<(K, V)> {
+        let keys = std::mem::take(&mut self._keys);
+        let mut values = std::mem::take(&mut self._values);
+
+        let drained = keys
+            .iter()
+            .filter_map(|k| values.remove(k).map(|v| (k.to_owned(), v.value)))
+            .collect::<Vec<(K, V)>>();
+
+        return drained.into_iter();
+    }
 }
 
-impl<I, K, V> Debug for IndexedLinkedHashMap<I, K, V>
+impl<I, K, V, S> IndexedLinkedHashMap<I, K, V, S>
+where
+    I: Keys<K> + Default,
+    K: Eq + Hash + Clone,
+    V: Clone,
+    S: BuildHasher,
+{
+    /// Creates a new `IndexedLinkedHashMap` using `hasher` to hash keys, instead
+    /// of requiring `S: Default`. Lets embedded targets pick a cheap
+    /// non-DoS-resistant hasher, e.g. `collections::FnvBuildHasher`, without
+    /// paying for `std`'s randomized default. Only this constructor and `Debug`
+    /// drop the bound: the map's other methods, `get` and `set` included, still
+    /// require `S: Default`.
+    pub fn with_hasher(hasher: S) -> Self {
+        return IndexedLinkedHashMap {
+            _keys: I::default(),
+            _values: HashMap::with_hasher(hasher),
+            _capacity: None,
+        };
+    }
+}
+
+impl<I, K, V, S> IntoIterator for IndexedLinkedHashMap<I, K, V, S>
+where
+    I: Keys<K> + Default,
+    K: Eq + Hash + Clone,
+    V: Clone,
+    S: BuildHasher + Default,
+{
+    type Item = (K, V);
+    type IntoIter = std::vec::IntoIter<(K, V)>;
+
+    /// Consumes the map, iterating key/value pairs in insertion order.
+    fn into_iter(mut self) -> Self::IntoIter {
+        return self.drain();
+    }
+}
+
+/// A view into a single entry, which may or may not be present, obtained from
+/// `IndexedLinkedHashMap::entry`.
+pub enum Entry<'a, I, K, V> {
+    Occupied(OccupiedEntry<'a, K, V>),
+    Vacant(VacantEntry<'a, I, K, V>),
+}
+
+impl<'a, I, K, V> Entry<'a, I, K, V>
+where
+    I: Keys<K>,
+    K: Eq + Hash + Clone,
+{
+    /// Ensures a value is present, inserting `default` if the entry is vacant, and
+    /// returns a mutable reference to the value, or `None` if the entry was
+    /// vacant and `_keys` is a capacity-bounded backing (e.g.
+    /// `collections::ArrayKeys`) that is already full, the same condition
+    /// `VacantEntry::insert` reports.
+    pub fn or_insert(self, default: V) -> Option<&'a mut V> {
+        return match self {
+            Entry::Occupied(entry) => Some(entry.into_mut()),
+            Entry::Vacant(entry) => entry.insert(default),
+        };
+    }
+
+    /// Ensures a value is present, inserting the result of `default` if the entry
+    /// is vacant, and returns a mutable reference to the value, or `None` under
+    /// the same capacity-bounded condition as `or_insert`.
+    pub fn or_insert_with<F>(self, default: F) -> Option<&'a mut V>
+    where
+        F: FnOnce() -> V,
+    {
+        return match self {
+            Entry::Occupied(entry) => Some(entry.into_mut()),
+            Entry::Vacant(entry) => entry.insert(default()),
+        };
+    }
+
+    /// Applies `f` to the value if the entry is occupied, then returns the entry
+    /// unchanged so it can be chained into `or_insert`/`or_insert_with`.
+    pub fn and_modify<F>(self, f: F) -> Self
+    where
+        F: FnOnce(&mut V),
+    {
+        return match self {
+            Entry::Occupied(mut entry) => {
+                f(entry.get_mut());
+                Entry::Occupied(entry)
+            }
+            Entry::Vacant(entry) => Entry::Vacant(entry),
+        };
+    }
+}
+
+/// An occupied entry, as part of the `Entry` API.
+pub struct OccupiedEntry<'a, K, V> {
+    inner: hash_map::OccupiedEntry<'a, K, IndexedLinkedHashMapValue<V>>,
+}
+
+impl<'a, K, V> OccupiedEntry<'a, K, V> {
+    /// Gets a reference to the value.
+    pub fn get(&self) -> &V {
+        return &self.inner.get().value;
+    }
+
+    /// Gets a mutable reference to the value.
+    pub fn get_mut(&mut self) -> &mut V {
+        return &mut self.inner.get_mut().value;
+    }
+
+    /// Converts into a mutable reference to the value bound to the entry's lifetime.
+    pub fn into_mut(self) -> &'a mut V {
+        return &mut self.inner.into_mut().value;
+    }
+}
+
+/// A vacant entry, as part of the `Entry` API.
+pub struct VacantEntry<'a, I, K, V> {
+    inner: hash_map::VacantEntry<'a, K, IndexedLinkedHashMapValue<V>>,
+    keys: &'a mut I,
+}
+
+impl<'a, I, K, V> VacantEntry<'a, I, K, V>
+where
+    I: Keys<K>,
+    K: Eq + Hash + Clone,
+{
+    /// Pushes the key onto `_keys` exactly like `set`, inserts the value, and
+    /// returns a mutable reference to it. If `_keys` is a capacity-bounded
+    /// backing (e.g. `collections::ArrayKeys`) that is already full, the key
+    /// is not pushed and no value is inserted either, so `None` is returned
+    /// instead of leaving a `_values` entry with no corresponding key; prefer
+    /// `try_set` over `entry` when that distinction matters.
+    pub fn insert(self, value: V) -> Option<&'a mut V> {
+        if !self.keys.push(self.inner.key().clone()) {
+            return None;
+        }
+
+        let index = Some(self.keys.len() - 1);
+        return Some(&mut self.inner.insert(IndexedLinkedHashMapValue { index, value }).value);
+    }
+}
+
+impl<I, K, V, S> Debug for IndexedLinkedHashMap<I, K, V, S>
 where
     I: Keys<K> + Default,
     K: Eq + Hash + Clone + Debug,
     V: Clone + Debug,
+    S: BuildHasher,
 {
     fn fmt(&self, f: &mut Formatter) -> Result {
         let mut out: String = String::new();
@@ -191,10 +563,29 @@ pub mod traits {
     pub trait Keys<K> {
         fn get(&self, i: Option<usize>) -> Option<&K>;
         fn set(&mut self, i: Option<usize>, k: K);
-        fn push(&mut self, k: K);
+
+        /// Appends a key; returns whether it was actually appended. Capacity-
+        /// bounded backings like `collections::ArrayKeys` return `false` once
+        /// full instead of growing, so `IndexedLinkedHashMap::set` can refuse
+        /// to insert the value rather than storing it under a stale index.
+        #[must_use]
+        fn push(&mut self, k: K) -> bool;
+
         fn remove(&mut self, i: Option<usize>);
         fn clear(&mut self);
         fn len(&self) -> usize;
+
+        /// Iterates keys in the same order used by `get`/`set`.
+        fn iter<'a>(&'a self) -> impl Iterator<Item = &'a K>
+        where
+            K: 'a;
+
+        /// Maximum number of keys this backing can hold, or `None` if it grows
+        /// without bound. Used by `IndexedLinkedHashMap::try_set` to fail instead
+        /// of silently dropping a key that `push` can't fit.
+        fn capacity(&self) -> Option<usize> {
+            return None;
+        }
     }
 }
 
@@ -225,8 +616,10 @@ pub mod collections {
             };
         }
 
-        fn push(&mut self, k: K) {
+        fn push(&mut self, k: K) -> bool {
             self.push(k);
+
+            return true;
         }
 
         fn remove(&mut self, i: Option<usize>) {
@@ -248,6 +641,13 @@ pub mod collections {
         fn len(&self) -> usize {
             return self.len();
         }
+
+        fn iter<'a>(&'a self) -> impl Iterator<Item = &'a K>
+        where
+            K: 'a,
+        {
+            return self.as_slice().iter();
+        }
     }
 
     impl<K> Keys<K> for BinaryHeap<K>
@@ -280,8 +680,10 @@ pub mod collections {
             self.append(&mut BinaryHeap::<K>::from(p));
         }
 
-        fn push(&mut self, k: K) {
+        fn push(&mut self, k: K) -> bool {
             self.push(k);
+
+            return true;
         }
 
         fn remove(&mut self, i: Option<usize>) {
@@ -311,6 +713,224 @@ pub mod collections {
         fn len(&self) -> usize {
             return self.len();
         }
+
+        fn iter<'a>(&'a self) -> impl Iterator<Item = &'a K>
+        where
+            K: 'a,
+        {
+            return self.iter();
+        }
+    }
+
+    /// A fixed-capacity, allocation-free `Keys` backing of const-generic capacity
+    /// `N`, in the spirit of `heapless`'s `IndexMap`. Unlike `Vec`, `push` past
+    /// capacity returns `false` instead of growing, so callers that need to know
+    /// about a full map should go through `IndexedLinkedHashMap::try_set` instead
+    /// of `set`.
+    ///
+    /// This only bounds `_keys`; `IndexedLinkedHashMap`'s value store is still a
+    /// `std::collections::HashMap`, which allocates and isn't available outside
+    /// `std`. So despite the "allocation-free" above, pairing `ArrayKeys` with
+    /// `IndexedLinkedHashMap` does not produce a map that avoids the heap or is
+    /// usable in `#![no_std]`.
+    pub struct ArrayKeys<K, const N: usize> {
+        items: [Option<K>; N],
+        len: usize,
+    }
+
+    impl<K, const N: usize> Default for ArrayKeys<K, N> {
+        fn default() -> Self {
+            return ArrayKeys {
+                items: std::array::from_fn(|_| None),
+                len: 0,
+            };
+        }
+    }
+
+    impl<K, const N: usize> Keys<K> for ArrayKeys<K, N> {
+        fn get(&self, i: Option<usize>) -> Option<&K> {
+            return match i {
+                Some(i) => match i >= self.len {
+                    true => None,
+                    false => self.items[i].as_ref(),
+                },
+                None => None,
+            };
+        }
+
+        fn set(&mut self, i: Option<usize>, k: K) {
+            match i {
+                Some(i) => match i >= self.len {
+                    true => (),
+                    false => {
+                        self.items[i] = Some(k);
+                    }
+                },
+                None => (),
+            };
+        }
+
+        fn push(&mut self, k: K) -> bool {
+            if self.len >= N {
+                return false;
+            }
+
+            self.items[self.len] = Some(k);
+            self.len += 1;
+
+            return true;
+        }
+
+        fn remove(&mut self, i: Option<usize>) {
+            match i {
+                Some(i) => match i >= self.len {
+                    true => (),
+                    false => {
+                        for shift in i..self.len - 1 {
+                            self.items[shift] = self.items[shift + 1].take();
+                        }
+
+                        self.items[self.len - 1] = None;
+                        self.len -= 1;
+                    }
+                },
+                None => (),
+            };
+        }
+
+        fn clear(&mut self) {
+            self.items = std::array::from_fn(|_| None);
+            self.len = 0;
+        }
+
+        fn len(&self) -> usize {
+            return self.len;
+        }
+
+        fn iter<'a>(&'a self) -> impl Iterator<Item = &'a K>
+        where
+            K: 'a,
+        {
+            return self.items[..self.len].iter().filter_map(Option::as_ref);
+        }
+
+        fn capacity(&self) -> Option<usize> {
+            return Some(N);
+        }
+    }
+
+    /// A cheap, non-cryptographic hasher (FNV-1a), useful as `S` on embedded
+    /// targets that want to avoid `std`'s randomized `SipHash` default and don't
+    /// need DoS resistance.
+    pub struct FnvHasher(u64);
+
+    impl Default for FnvHasher {
+        fn default() -> Self {
+            return FnvHasher(0xcbf29ce484222325);
+        }
+    }
+
+    impl std::hash::Hasher for FnvHasher {
+        fn write(&mut self, bytes: &[u8]) {
+            for byte in bytes {
+                self.0 ^= *byte as u64;
+                self.0 = self.0.wrapping_mul(0x100000001b3);
+            }
+        }
+
+        fn finish(&self) -> u64 {
+            return self.0;
+        }
+    }
+
+    /// `std::hash::BuildHasher` for `FnvHasher`, to pass to
+    /// `IndexedLinkedHashMap::with_hasher`.
+    #[derive(Clone, Copy, Default)]
+    pub struct FnvBuildHasher;
+
+    impl std::hash::BuildHasher for FnvBuildHasher {
+        type Hasher = FnvHasher;
+
+        fn build_hasher(&self) -> FnvHasher {
+            return FnvHasher::default();
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_support {
+    use super::{traits::Keys, IndexedLinkedHashMap};
+    use serde::de::{Deserialize, Deserializer, MapAccess, Visitor};
+    use serde::ser::{Serialize, SerializeMap, Serializer};
+    use std::fmt;
+    use std::hash::{BuildHasher, Hash};
+    use std::marker::PhantomData;
+
+    impl<I, K, V, H> Serialize for IndexedLinkedHashMap<I, K, V, H>
+    where
+        I: Keys<K> + Default,
+        K: Eq + Hash + Clone + Serialize,
+        V: Clone + Serialize,
+        H: BuildHasher + Default,
+    {
+        /// Serializes as a map, writing entries in `_keys` order so round-tripping
+        /// through JSON (or any other ordered `serde` format) preserves it.
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let mut map = serializer.serialize_map(Some(self.len()))?;
+            for (k, v) in self.iter() {
+                map.serialize_entry(k, v)?;
+            }
+
+            return map.end();
+        }
+    }
+
+    impl<'de, I, K, V, H> Deserialize<'de> for IndexedLinkedHashMap<I, K, V, H>
+    where
+        I: Keys<K> + Default,
+        K: Eq + Hash + Clone + Deserialize<'de>,
+        V: Clone + Deserialize<'de>,
+        H: BuildHasher + Default,
+    {
+        /// Deserializes by replaying `set` for each entry in the order it was
+        /// read, so insertion order and indices come back exactly as serialized.
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            return deserializer.deserialize_map(IndexedLinkedHashMapVisitor(PhantomData));
+        }
+    }
+
+    struct IndexedLinkedHashMapVisitor<I, K, V, H>(PhantomData<(I, K, V, H)>);
+
+    impl<'de, I, K, V, H> Visitor<'de> for IndexedLinkedHashMapVisitor<I, K, V, H>
+    where
+        I: Keys<K> + Default,
+        K: Eq + Hash + Clone + Deserialize<'de>,
+        V: Clone + Deserialize<'de>,
+        H: BuildHasher + Default,
+    {
+        type Value = IndexedLinkedHashMap<I, K, V, H>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            return formatter.write_str("a map of key/value pairs in insertion order");
+        }
+
+        fn visit_map<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let mut ins = IndexedLinkedHashMap::new();
+            while let Some((k, v)) = access.next_entry()? {
+                ins.set(k, v);
+            }
+
+            return Ok(ins);
+        }
     }
 }
 
@@ -335,6 +955,24 @@ mod tests {
             assert!(ins.get(&"k") == Some(&1));
         }
 
+        #[test]
+        fn get_borrowed() {
+            let mut ins = IndexedLinkedHashMap::<Vec<String>, String, usize>::new();
+            ins.set("k".to_owned(), 1);
+
+            // `&str` is a borrowed form of `String`, so no owned key is allocated here.
+            assert!(ins.get("k") == Some(&1));
+            assert!(ins.contains_key("k") == true);
+            assert!(
+                ins.remove("k")
+                    == Some(IndexedLinkedHashMapValue {
+                        index: Some(0),
+                        value: 1
+                    })
+            );
+            assert!(ins.get("k") == None);
+        }
+
         #[test]
         fn set() {
             let mut ins = IndexedLinkedHashMap::<Vec<&str>, &str, usize>::new();
@@ -345,6 +983,85 @@ mod tests {
             assert!(ins.get("k") == Some(&1));
         }
 
+        #[test]
+        fn try_set() {
+            let mut ins = IndexedLinkedHashMap::<collections::ArrayKeys<&str, 2>, &str, usize>::new();
+            assert!(ins.try_set("a", 1) == Ok(()));
+            assert!(ins.try_set("b", 2) == Ok(()));
+            assert!(ins.try_set("c", 3) == Err(("c", 3)));
+            assert!(ins.len() == 2);
+
+            // Overwriting an existing key never consults capacity, even when full.
+            assert!(ins.try_set("a", 4) == Ok(()));
+            assert!(ins.get("a") == Some(&4));
+        }
+
+        #[test]
+        fn set_at_capacity_does_not_corrupt_indices() {
+            let mut ins = IndexedLinkedHashMap::<collections::ArrayKeys<&str, 2>, &str, usize>::new();
+            ins.set("a", 1);
+            ins.set("b", 2);
+
+            // Past capacity, `set` is a no-op, the same as `Keys::push`, instead
+            // of inserting a value whose index aliases an existing key's slot.
+            ins.set("c", 3);
+            assert!(ins.len() == 2);
+            assert!(ins.get("c") == None);
+            assert!(ins.keys().iter().collect::<Vec<&&str>>() == vec![&"a", &"b"]);
+            for value in ins.values() {
+                assert!(ins.at(value.index) == Some(&value.value));
+            }
+        }
+
+        #[test]
+        fn entry_at_capacity_does_not_corrupt_indices() {
+            let mut ins = IndexedLinkedHashMap::<collections::ArrayKeys<&str, 2>, &str, usize>::new();
+            ins.entry("a").or_insert(1).unwrap();
+            ins.entry("b").or_insert(2).unwrap();
+
+            // Past capacity, `entry`'s vacant insert is a no-op, the same as
+            // `set`, instead of leaving a `_values` entry with no key.
+            assert!(ins.entry("c").or_insert(3).is_none());
+            assert!(ins.len() == 2);
+            assert!(ins.values().len() == 2);
+            assert!(ins.iter().count() == 2);
+            assert!(ins.contains_key("c") == false);
+            assert!(ins.get("c") == None);
+            assert!(ins.keys().iter().collect::<Vec<&&str>>() == vec![&"a", &"b"]);
+        }
+
+        #[test]
+        fn with_hasher() {
+            let mut ins = IndexedLinkedHashMap::<Vec<&str>, &str, usize, collections::FnvBuildHasher>::with_hasher(
+                collections::FnvBuildHasher,
+            );
+            ins.set("k", 1);
+            assert!(ins.get("k") == Some(&1));
+        }
+
+        #[test]
+        fn entry() {
+            let mut ins = IndexedLinkedHashMap::<Vec<&str>, &str, usize>::new();
+            ins.entry("k").or_insert(1).unwrap();
+            assert!(ins.get("k") == Some(&1));
+            assert!(ins.key_at(Some(0)) == Some(&"k"));
+
+            ins.entry("k").or_insert(2).unwrap();
+            assert!(ins.get("k") == Some(&1));
+
+            ins.entry("k").and_modify(|v| *v += 1).or_insert(0).unwrap();
+            assert!(ins.get("k") == Some(&2));
+
+            ins.entry("other").and_modify(|v| *v += 1).or_insert(5).unwrap();
+            assert!(ins.get("other") == Some(&5));
+            assert!(ins.key_at(Some(1)) == Some(&"other"));
+
+            let mut groups = IndexedLinkedHashMap::<Vec<&str>, &str, Vec<usize>>::new();
+            groups.entry("k").or_insert_with(Vec::new).unwrap().push(7);
+            groups.entry("k").or_insert_with(Vec::new).unwrap().push(8);
+            assert!(groups.get("k") == Some(&vec![7, 8]));
+        }
+
         #[test]
         fn at() {
             let mut ins = IndexedLinkedHashMap::<Vec<&str>, &str, usize>::new();
@@ -394,6 +1111,173 @@ mod tests {
             assert!(ins.values().len() == 0);
         }
 
+        #[test]
+        fn shift_remove() {
+            let mut ins = IndexedLinkedHashMap::<Vec<&str>, &str, usize>::new();
+            ins.set("a", 1);
+            ins.set("b", 2);
+            ins.set("c", 3);
+
+            assert!(
+                ins.shift_remove("b")
+                    == Some(IndexedLinkedHashMapValue {
+                        index: Some(1),
+                        value: 2
+                    })
+            );
+            assert!(ins.at(Some(0)) == Some(&1));
+            assert!(ins.at(Some(1)) == Some(&3));
+            assert!(ins.key_at(Some(0)) == Some(&"a"));
+            assert!(ins.key_at(Some(1)) == Some(&"c"));
+            for value in ins.values() {
+                assert!(ins.at(value.index) == Some(&value.value));
+            }
+
+            assert!(
+                ins.shift_remove("a")
+                    == Some(IndexedLinkedHashMapValue {
+                        index: Some(0),
+                        value: 1
+                    })
+            );
+            assert!(ins.at(Some(0)) == Some(&3));
+            assert!(ins.key_at(Some(0)) == Some(&"c"));
+        }
+
+        #[test]
+        fn swap_remove() {
+            let mut ins = IndexedLinkedHashMap::<Vec<&str>, &str, usize>::new();
+            ins.set("a", 1);
+            ins.set("b", 2);
+            ins.set("c", 3);
+
+            assert!(
+                ins.swap_remove("a")
+                    == Some(IndexedLinkedHashMapValue {
+                        index: Some(0),
+                        value: 1
+                    })
+            );
+            assert!(ins.len() == 2);
+            assert!(ins.at(Some(0)) == Some(&3));
+            assert!(ins.key_at(Some(0)) == Some(&"c"));
+            assert!(ins.at(Some(1)) == Some(&2));
+            for value in ins.values() {
+                assert!(ins.at(value.index) == Some(&value.value));
+            }
+
+            assert!(
+                ins.swap_remove("c")
+                    == Some(IndexedLinkedHashMapValue {
+                        index: Some(0),
+                        value: 3
+                    })
+            );
+            assert!(ins.len() == 1);
+            assert!(ins.at(Some(0)) == Some(&2));
+            assert!(ins.key_at(Some(0)) == Some(&"b"));
+        }
+
+        #[test]
+        fn pop_front() {
+            let mut ins = IndexedLinkedHashMap::<Vec<&str>, &str, usize>::new();
+            assert!(ins.pop_front() == None);
+
+            ins.set("a", 1);
+            ins.set("b", 2);
+            ins.set("c", 3);
+
+            assert!(ins.pop_front() == Some(("a", 1)));
+            assert!(ins.len() == 2);
+            assert!(ins.key_at(Some(0)) == Some(&"b"));
+            assert!(ins.pop_front() == Some(("b", 2)));
+            assert!(ins.pop_front() == Some(("c", 3)));
+            assert!(ins.pop_front() == None);
+        }
+
+        #[test]
+        fn touch() {
+            let mut ins = IndexedLinkedHashMap::<Vec<&str>, &str, usize>::new();
+            ins.set("a", 1);
+            ins.set("b", 2);
+            ins.set("c", 3);
+
+            // Touching "a" moves it past "b" and "c", the newer insertions.
+            assert!(ins.touch("a") == true);
+            assert!(ins.key_at(Some(0)) == Some(&"b"));
+            assert!(ins.key_at(Some(1)) == Some(&"c"));
+            assert!(ins.key_at(Some(2)) == Some(&"a"));
+            assert!(ins.get("a") == Some(&1));
+
+            assert!(ins.touch("missing") == false);
+        }
+
+        #[test]
+        fn get_refresh() {
+            let mut ins = IndexedLinkedHashMap::<Vec<&str>, &str, usize>::new();
+            ins.set("a", 1);
+            ins.set("b", 2);
+
+            assert!(ins.get_refresh("a") == Some(&1));
+            assert!(ins.key_at(Some(0)) == Some(&"b"));
+            assert!(ins.key_at(Some(1)) == Some(&"a"));
+        }
+
+        #[test]
+        fn with_capacity_lru() {
+            let mut ins = IndexedLinkedHashMap::<Vec<&str>, &str, usize>::with_capacity_lru(2);
+            ins.set("a", 1);
+            ins.set("b", 2);
+
+            // "c" pushes the map past capacity, evicting the least-recently-used
+            // entry, "a".
+            ins.set("c", 3);
+            assert!(ins.len() == 2);
+            assert!(ins.get("a") == None);
+            assert!(ins.key_at(Some(0)) == Some(&"b"));
+            assert!(ins.key_at(Some(1)) == Some(&"c"));
+
+            // Touching "b" promotes it past "c", so the next eviction takes "c"
+            // instead.
+            ins.touch("b");
+            ins.set("d", 4);
+            assert!(ins.len() == 2);
+            assert!(ins.get("c") == None);
+            assert!(ins.get("b") == Some(&2));
+            assert!(ins.get("d") == Some(&4));
+        }
+
+        #[test]
+        fn with_capacity_lru_overwrite_refreshes_recency() {
+            let mut ins = IndexedLinkedHashMap::<Vec<&str>, &str, usize>::with_capacity_lru(2);
+            ins.set("a", 1);
+            ins.set("b", 2);
+
+            // Overwriting "a" counts as a use, so the next eviction takes "b",
+            // the untouched key, instead.
+            ins.set("a", 100);
+            ins.set("c", 3);
+            assert!(ins.len() == 2);
+            assert!(ins.get("b") == None);
+            assert!(ins.get("a") == Some(&100));
+            assert!(ins.get("c") == Some(&3));
+        }
+
+        #[test]
+        fn with_capacity_lru_enforced_through_entry() {
+            let mut ins = IndexedLinkedHashMap::<Vec<&str>, &str, usize>::with_capacity_lru(2);
+            ins.entry("a").or_insert(1).unwrap();
+            ins.entry("b").or_insert(2).unwrap();
+
+            // A vacant entry for a new key evicts the least-recently-used entry
+            // up front, the same as `set`, instead of bypassing the bound.
+            ins.entry("c").or_insert(3).unwrap();
+            assert!(ins.len() == 2);
+            assert!(ins.get("a") == None);
+            assert!(ins.get("b") == Some(&2));
+            assert!(ins.get("c") == Some(&3));
+        }
+
         #[test]
         fn clear() {
             let mut ins = IndexedLinkedHashMap::<Vec<&str>, &str, usize>::new();
@@ -451,6 +1335,57 @@ mod tests {
             assert!(ins.values().eq(&values));
         }
 
+        #[test]
+        fn iter() {
+            let mut ins = IndexedLinkedHashMap::<Vec<&str>, &str, usize>::new();
+            assert!(ins.iter().collect::<Vec<(&&str, &usize)>>() == Vec::new());
+
+            ins.set("a", 1);
+            ins.set("b", 2);
+            ins.set("c", 3);
+            ins.shift_remove("a");
+            ins.set("d", 4);
+
+            assert!(
+                ins.iter().collect::<Vec<(&&str, &usize)>>()
+                    == vec![(&"b", &2), (&"c", &3), (&"d", &4)]
+            );
+        }
+
+        #[test]
+        fn iter_mut() {
+            let mut ins = IndexedLinkedHashMap::<Vec<&str>, &str, usize>::new();
+            ins.set("a", 1);
+            ins.set("b", 2);
+
+            for (_, v) in ins.iter_mut() {
+                *v += 10;
+            }
+
+            assert!(ins.iter().collect::<Vec<(&&str, &usize)>>() == vec![(&"a", &11), (&"b", &12)]);
+        }
+
+        #[test]
+        fn drain() {
+            let mut ins = IndexedLinkedHashMap::<Vec<&str>, &str, usize>::new();
+            ins.set("a", 1);
+            ins.set("b", 2);
+
+            assert!(ins.drain().collect::<Vec<(&str, usize)>>() == vec![("a", 1), ("b", 2)]);
+            assert!(ins.len() == 0);
+            assert!(ins.keys().len() == 0);
+            assert!(ins.values().len() == 0);
+        }
+
+        #[test]
+        fn into_iter() {
+            let mut ins = IndexedLinkedHashMap::<Vec<&str>, &str, usize>::new();
+            ins.set("a", 1);
+            ins.set("b", 2);
+
+            assert!(ins.into_iter().collect::<Vec<(&str, usize)>>() == vec![("a", 1), ("b", 2)]);
+        }
+
         mod debug {
             use crate::*;
 
@@ -464,6 +1399,34 @@ mod tests {
             }
         }
 
+        #[cfg(feature = "serde")]
+        mod serde {
+            use crate::*;
+
+            #[test]
+            fn round_trip_preserves_order() {
+                let mut ins = IndexedLinkedHashMap::<Vec<String>, String, usize>::new();
+                ins.set("c".to_owned(), 3);
+                ins.set("a".to_owned(), 1);
+                ins.set("b".to_owned(), 2);
+
+                let json = serde_json::to_string(&ins).unwrap();
+                assert!(json == "{\"c\":3,\"a\":1,\"b\":2}");
+
+                let round_tripped: IndexedLinkedHashMap<Vec<String>, String, usize> =
+                    serde_json::from_str(&json).unwrap();
+                assert!(
+                    round_tripped
+                        .iter()
+                        .collect::<Vec<(&String, &usize)>>()
+                        == ins.iter().collect::<Vec<(&String, &usize)>>()
+                );
+                assert!(round_tripped.key_at(Some(0)) == Some(&"c".to_owned()));
+                assert!(round_tripped.key_at(Some(1)) == Some(&"a".to_owned()));
+                assert!(round_tripped.key_at(Some(2)) == Some(&"b".to_owned()));
+            }
+        }
+
         mod performance {
             use crate::*;
             use rand::distributions::{Alphanumeric, DistString};
@@ -516,15 +1479,15 @@ mod tests {
                         let k: String = get_random_string();
                         let v: String = get_random_string();
                         ins.set(k.to_owned(), v.to_owned());
-                        ins.get(k.to_owned());
+                        ins.get(&k);
                         ins.at(Some(i));
                         ins.key_at(Some(i));
                         ins.set_at(Some(i), k.to_owned(), v.to_owned());
                         ins.len();
-                        ins.contains_key(k.to_owned());
+                        ins.contains_key(&k);
                         ins.keys();
                         ins.values();
-                        ins.remove(k.to_owned());
+                        ins.remove(&k);
                         ins.set(k, v);
                     }
                     ins.clear();
@@ -534,4 +1497,39 @@ mod tests {
             }
         }
     }
+
+    mod collections {
+        use crate::collections::*;
+        use crate::traits::Keys;
+
+        #[test]
+        fn array_keys_capacity() {
+            let mut keys = ArrayKeys::<&str, 2>::default();
+            assert!(keys.capacity() == Some(2));
+            assert!(keys.push("a") == true);
+            assert!(keys.push("b") == true);
+            assert!(keys.len() == 2);
+
+            // Past capacity, `push` reports failure and is a no-op rather than
+            // panicking or growing.
+            assert!(keys.push("c") == false);
+            assert!(keys.len() == 2);
+            assert!(keys.iter().collect::<Vec<&&str>>() == vec![&"a", &"b"]);
+        }
+
+        #[test]
+        fn fnv_build_hasher_deterministic() {
+            use std::hash::{BuildHasher, Hash, Hasher};
+
+            fn hash_of<T: Hash>(hasher: &FnvBuildHasher, value: T) -> u64 {
+                let mut h = hasher.build_hasher();
+                value.hash(&mut h);
+                return h.finish();
+            }
+
+            let hasher = FnvBuildHasher;
+            assert!(hash_of(&hasher, "k") == hash_of(&hasher, "k"));
+            assert!(hash_of(&hasher, "k") != hash_of(&hasher, "other"));
+        }
+    }
 }